@@ -0,0 +1,136 @@
+//! Hierarchical merge of YAML config files, used for both `cloud.cfg`
+//! (`datasource_list`) and `ds-identify.cfg` (`datasource`/`policy`).
+//!
+//! Unlike the old line-oriented scanner this actually parses each file as
+//! YAML and deep-merges them in precedence order: scalars and sequences
+//! from later files overwrite earlier ones, while maps are merged key by
+//! key. This mirrors cloud-init's own config-layering semantics instead of
+//! "last file that mentions the key wins, whole line at a time".
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde_yaml::Value;
+
+use crate::context::Context;
+use crate::logging::Logger;
+
+#[derive(Debug, Default)]
+pub struct Config {
+    merged: Value,
+    /// the last file that contributed a value for a given top-level key,
+    /// so callers can keep logging which source supplied a value.
+    sources: HashMap<String, PathBuf>,
+}
+
+impl Config {
+    /// Parse and deep-merge every file in `paths`, in order, through `ctx`
+    /// so this is exercisable from a [`crate::context::MockContext`] fixture
+    /// instead of the real filesystem. A missing file is skipped; an
+    /// unreadable or unparsable one is logged and skipped rather than
+    /// aborting the whole merge,
+    /// matching this crate's warn-and-continue policy for config handling.
+    pub fn read(ctx: &dyn Context, logger: &Logger, paths: &[PathBuf]) -> Self {
+        let mut config = Self::default();
+        for path in paths {
+            if !ctx.exists(path) {
+                continue;
+            }
+            let content = match ctx.read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    logger.warn(format!("failed to read {path:?}: {e}. skipping it."));
+                    continue;
+                }
+            };
+            let doc: Value = match serde_yaml::from_str(&content) {
+                Ok(doc) => doc,
+                Err(e) => {
+                    logger.warn(format!("failed to parse {path:?} as yaml: {e}. skipping it."));
+                    continue;
+                }
+            };
+            config.merge_from(path, doc);
+        }
+        config
+    }
+
+    fn merge_from(&mut self, path: &Path, doc: Value) {
+        if let Some(map) = doc.as_mapping() {
+            for key in map.keys().filter_map(Value::as_str) {
+                self.sources.insert(key.to_string(), path.to_owned());
+            }
+        }
+        deep_merge(&mut self.merged, doc);
+    }
+
+    /// the `datasource_list` key, and the file that last set it.
+    pub fn datasource_list(&self) -> Option<(Vec<String>, &Path)> {
+        let list = self
+            .merged
+            .get("datasource_list")?
+            .as_sequence()?
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect();
+        let path = self.sources.get("datasource_list")?;
+        Some((list, path))
+    }
+
+    /// the `datasource` key, and the file that last set it.
+    pub fn dsname(&self) -> Option<(String, &Path)> {
+        self.scalar("datasource")
+    }
+
+    /// the `policy` key, and the file that last set it.
+    pub fn policy(&self) -> Option<(String, &Path)> {
+        self.scalar("policy")
+    }
+
+    fn scalar(&self, key: &str) -> Option<(String, &Path)> {
+        let value = self.merged.get(key)?.as_str()?.to_string();
+        let path = self.sources.get(key)?;
+        Some((value, path))
+    }
+}
+
+fn deep_merge(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Mapping(base_map), Value::Mapping(other_map)) => {
+            for (key, value) in other_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base_slot, other) => *base_slot = other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deep_merge_overwrites_scalars() {
+        let mut base: Value = serde_yaml::from_str("a: 1\nb: 2").unwrap();
+        let other: Value = serde_yaml::from_str("b: 3").unwrap();
+        deep_merge(&mut base, other);
+        assert_eq!(base.get("a").unwrap().as_i64(), Some(1));
+        assert_eq!(base.get("b").unwrap().as_i64(), Some(3));
+    }
+
+    #[test]
+    fn test_deep_merge_recurses_into_maps() {
+        let mut base: Value = serde_yaml::from_str("datasource:\n  Ec2:\n    timeout: 5").unwrap();
+        let other: Value =
+            serde_yaml::from_str("datasource:\n  Ec2:\n    max_wait: 10").unwrap();
+        deep_merge(&mut base, other);
+        let ec2 = &base["datasource"]["Ec2"];
+        assert_eq!(ec2["timeout"].as_i64(), Some(5));
+        assert_eq!(ec2["max_wait"].as_i64(), Some(10));
+    }
+}
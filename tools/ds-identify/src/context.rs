@@ -0,0 +1,79 @@
+//! Injectable environment/filesystem access. Threading a `&dyn Context`
+//! through the detection path lets it be driven from an in-memory fixture
+//! instead of mutating process-global env vars, which is not safe across
+//! parallel tests.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub trait Context {
+    fn env(&self, key: &str) -> Option<String>;
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+    fn exists(&self, path: &Path) -> bool;
+}
+
+/// reads from the real process environment and filesystem.
+pub struct SystemContext;
+
+impl Context for SystemContext {
+    fn env(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+}
+
+/// an in-memory stand-in for [`SystemContext`], for tests that need a
+/// particular env var or file layout without touching the real ones.
+#[derive(Debug, Default)]
+pub struct MockContext {
+    pub env: HashMap<String, String>,
+    pub files: HashMap<PathBuf, String>,
+}
+
+impl Context for MockContext {
+    fn env(&self, key: &str) -> Option<String> {
+        self.env.get(key).cloned()
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{path:?} not found")))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_context_env_and_files() {
+        let mut ctx = MockContext::default();
+        ctx.env.insert("PATH_ROOT".to_string(), "/fixture".to_string());
+        ctx.files
+            .insert(PathBuf::from("/fixture/etc/cloud/ds-identify.cfg"), "datasource: NoCloud\n".to_string());
+
+        assert_eq!(Some("/fixture".to_string()), ctx.env("PATH_ROOT"));
+        assert_eq!(None, ctx.env("PATH_RUN"));
+        assert!(ctx.exists(Path::new("/fixture/etc/cloud/ds-identify.cfg")));
+        assert!(!ctx.exists(Path::new("/fixture/etc/cloud/missing.cfg")));
+        assert_eq!(
+            "datasource: NoCloud\n",
+            ctx.read_to_string(Path::new("/fixture/etc/cloud/ds-identify.cfg"))
+                .unwrap()
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::info::Info;
 use std::{fs, os::unix::fs::FileTypeExt};
 
+mod registry;
+
 #[derive(Debug, Clone)]
 pub enum Datasource {
     None,
@@ -17,12 +19,15 @@ pub enum DscheckResult {
 }
 
 impl Datasource {
-    pub fn dscheck_fn(&self) -> fn(&Info) -> DscheckResult {
-        match &self {
-            Self::None => dscheck_none,
-            Self::NoCloud => dscheck_no_cloud,
-            Self::LXD => dscheck_lxd,
-            _ => todo!(),
+    /// the check for this datasource, or `None` if it has no check method
+    /// (neither a dedicated variant below nor an entry in the [`registry`]
+    /// built for this binary's enabled Cargo features).
+    pub fn dscheck_fn(&self) -> Option<fn(&Info) -> DscheckResult> {
+        match self {
+            Self::None => Some(dscheck_none),
+            Self::NoCloud => Some(dscheck_no_cloud),
+            Self::LXD => Some(dscheck_lxd),
+            Self::Unknown(name) => registry::lookup(name),
         }
     }
 }
@@ -44,7 +49,7 @@ impl From<&Datasource> for String {
             Datasource::NoCloud => "NoCloud".to_string(),
             Datasource::None => "None".to_string(),
             Datasource::LXD => "LXD".to_string(),
-            Datasource::Unknown(ds) => format!("Unknown({})", ds),
+            Datasource::Unknown(ds) => ds.clone(),
         }
     }
 }
@@ -62,7 +67,7 @@ fn dscheck_no_cloud(info: &Info) -> DscheckResult {
     }
 
     if let Some(produc_serial) = &info.smbios().product_serial {
-        if produc_serial.contains(DS_NOCLOUD) {
+        if produc_serial.to_string_lossy().contains(DS_NOCLOUD) {
             return DscheckResult::Found(None);
         }
     }
@@ -89,7 +94,7 @@ fn dscheck_lxd(info: &Info) -> DscheckResult {
     // https://github.com/systemd/systemd/issues/22709
     if info.virt() == "kvm" || info.virt() == "qemu" {
         if let Some(board_name) = &info.smbios().board_name {
-            if board_name == "LXD" {
+            if board_name.to_string_lossy() == "LXD" {
                 return DscheckResult::Found(None);
             }
         }
@@ -97,12 +102,53 @@ fn dscheck_lxd(info: &Info) -> DscheckResult {
     DscheckResult::NotFound
 }
 
-fn dscheck_cloud_stack(_info: &Info) -> DscheckResult {
-    todo!();
+#[cfg(feature = "configdrive")]
+fn dscheck_config_drive(info: &Info) -> DscheckResult {
+    if util::check_seed_dir(info.paths(), "config_drive", None) {
+        return DscheckResult::Found(None);
+    }
+    DscheckResult::NotFound
+}
+
+#[cfg(feature = "ec2")]
+fn dscheck_ec2(info: &Info) -> DscheckResult {
+    let smbios = info.smbios();
+
+    let is_amazon_vendor = smbios
+        .sys_vendor
+        .as_deref()
+        .map(|v| v.to_string_lossy().trim().eq_ignore_ascii_case("Amazon"))
+        .unwrap_or(false);
+    if is_amazon_vendor {
+        return DscheckResult::Found(None);
+    }
+
+    // bare-metal instances don't set sys_vendor to "Amazon", but do set the
+    // DMI product name to a per-instance-type string ending in ".metal".
+    let is_bare_metal = smbios
+        .product_name
+        .as_deref()
+        .map(|name| name.to_string_lossy().trim().ends_with(".metal"))
+        .unwrap_or(false);
+    if is_bare_metal {
+        return DscheckResult::Maybe(None);
+    }
+
+    DscheckResult::NotFound
+}
+
+#[cfg(feature = "ubuntucore")]
+fn dscheck_ubuntu_core(info: &Info) -> DscheckResult {
+    if util::check_writable_seed_dir(info.paths()) {
+        return DscheckResult::Found(None);
+    }
+    DscheckResult::NotFound
 }
 
 mod util {
     use crate::paths::Paths;
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
 
     /// check the seed dir /var/lib/cloud/seed/<name> for 'required'
     /// required defaults to 'meta-data'
@@ -124,12 +170,16 @@ mod util {
         // ubuntu core bind-mounts /writable/system-data/var/lib/cloud
         // over the top of /var/lib/cloud, but the mount might not be done yet.
         const WDIR: &str = "writable/system-data";
-        if !paths.root.join(WDIR).is_dir() {
+        let source = paths.root().join(WDIR).join("var/lib/cloud");
+        if !source.is_dir() {
             return false;
         }
 
-        // TODO
-
-        true
+        // confirm the bind mount actually happened by checking both paths
+        // share a device.
+        match (fs::metadata(&source), fs::metadata(&paths.var_lib_cloud)) {
+            (Ok(src_meta), Ok(dst_meta)) => src_meta.dev() == dst_meta.dev(),
+            _ => false,
+        }
     }
 }
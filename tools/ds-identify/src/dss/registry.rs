@@ -0,0 +1,23 @@
+//! Name -> check lookup for every datasource beyond the three with a
+//! dedicated [`super::Datasource`] variant (`None`, `NoCloud`, `LXD`, which
+//! every build needs to make sense of `DatasourceList`). Each entry below is
+//! gated behind a Cargo feature named after the datasource, so a minimal
+//! build can ship checks for only the clouds it targets. A name with no
+//! matching feature enabled, or no entry at all, resolves to `None` and
+//! [`super::Datasource::dscheck_fn`] reports it as having no check method.
+
+use crate::info::Info;
+
+use super::DscheckResult;
+
+pub fn lookup(name: &str) -> Option<fn(&Info) -> DscheckResult> {
+    match &name.to_lowercase()[..] {
+        #[cfg(feature = "configdrive")]
+        "configdrive" => Some(super::dscheck_config_drive),
+        #[cfg(feature = "ec2")]
+        "ec2" => Some(super::dscheck_ec2),
+        #[cfg(feature = "ubuntucore")]
+        "ubuntucore" => Some(super::dscheck_ubuntu_core),
+        _ => None,
+    }
+}
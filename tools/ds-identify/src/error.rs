@@ -0,0 +1,80 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Crate-wide error type.
+///
+/// `ds-identify` runs very early in boot, often against unreadable configs
+/// or a read-only filesystem, so callers are expected to log the cause
+/// chain and degrade (skip a source, fall back to a default) rather than
+/// unwind. `main` is the one place that turns this into an `ExitCode`.
+#[derive(Debug)]
+pub enum DsIdentifyError {
+    Io { path: PathBuf, source: io::Error },
+    ConfigParse { path: PathBuf, reason: String },
+    LogInit { path: PathBuf, source: io::Error },
+    NotAbsolute { path: PathBuf },
+    InvalidPath { path: PathBuf, reason: String },
+}
+
+impl DsIdentifyError {
+    pub fn io<P: Into<PathBuf>>(path: P, source: io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub fn config_parse<P: Into<PathBuf>, R: Into<String>>(path: P, reason: R) -> Self {
+        Self::ConfigParse {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn log_init<P: Into<PathBuf>>(path: P, source: io::Error) -> Self {
+        Self::LogInit {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub fn not_absolute<P: Into<PathBuf>>(path: P) -> Self {
+        Self::NotAbsolute { path: path.into() }
+    }
+
+    pub fn invalid_path<P: Into<PathBuf>, R: Into<String>>(path: P, reason: R) -> Self {
+        Self::InvalidPath {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for DsIdentifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "I/O error on {path:?}: {source}"),
+            Self::ConfigParse { path, reason } => {
+                write!(f, "failed to parse config {path:?}: {reason}")
+            }
+            Self::LogInit { path, source } => {
+                write!(f, "failed to initialize log at {path:?}: {source}")
+            }
+            Self::NotAbsolute { path } => write!(f, "path {path:?} is not absolute"),
+            Self::InvalidPath { path, reason } => write!(f, "invalid path {path:?}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DsIdentifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::LogInit { source, .. } => Some(source),
+            Self::ConfigParse { .. } => None,
+            Self::NotAbsolute { .. } => None,
+            Self::InvalidPath { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! UEFI/legacy-BIOS boot-mode detection and EFI variable enumeration, read
+//! directly from `/sys/firmware/efi` and `/sys/firmware/efi/efivars` the way
+//! `bootctl`/`efivar` do. Gives datasource policies a signal to key on for
+//! platforms (e.g. Azure, GCE) that only boot one way or the other.
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct Firmware {
+    pub efi_boot: bool,
+    pub secure_boot: Option<bool>,
+    efi_vars: BTreeMap<OsString, OsString>,
+}
+
+impl Firmware {
+    pub(crate) fn read(sys_firmware_efi: &Path) -> Self {
+        if !sys_firmware_efi.is_dir() {
+            return Self {
+                efi_boot: false,
+                secure_boot: None,
+                efi_vars: BTreeMap::new(),
+            };
+        }
+
+        let efi_vars = Self::read_efi_vars(&sys_firmware_efi.join("efivars"));
+        let secure_boot = efi_vars.iter().find_map(|(name, value)| {
+            name.to_string_lossy()
+                .starts_with("SecureBoot-")
+                .then(|| value.as_bytes().last().copied())
+                .flatten()
+                .map(|b| b != 0)
+        });
+
+        Self {
+            efi_boot: true,
+            secure_boot,
+            efi_vars,
+        }
+    }
+
+    /// the EFI variable named `name-<vendor-guid>`, if present, with its
+    /// leading attributes word already stripped.
+    pub fn efi_var(&self, name: &str) -> Option<&OsStr> {
+        let prefix = format!("{name}-");
+        self.efi_vars
+            .iter()
+            .find(|(var_name, _)| var_name.to_string_lossy().starts_with(&prefix))
+            .map(|(_, value)| value.as_os_str())
+    }
+
+    /// named EFI variables under `efivars`, keyed by `<Name>-<vendor-guid>`
+    /// as the kernel exposes them, value already stripped of the leading
+    /// 4-byte attributes word the kernel prefixes onto each file's content.
+    fn read_efi_vars(efivars: &Path) -> BTreeMap<OsString, OsString> {
+        let mut vars = BTreeMap::new();
+        let Ok(entries) = fs::read_dir(efivars) else {
+            return vars;
+        };
+
+        for entry in entries {
+            let Ok(entry) = entry else { continue };
+            let Ok(raw) = fs::read(entry.path()) else {
+                continue;
+            };
+            if raw.len() < 4 {
+                continue;
+            }
+            vars.insert(
+                entry.file_name(),
+                OsStr::from_bytes(&raw[4..]).to_os_string(),
+            );
+        }
+
+        vars
+    }
+}
@@ -0,0 +1,242 @@
+//! Pure-Rust replacement for shelling out to `blkid -c /dev/null -o export`.
+//! Reads the first few KiB of each block device directly and recognizes the
+//! on-disk signatures cloud-init cares about (ISO9660, FAT, ext2/3/4). This
+//! keeps filesystem-label detection working on stripped containers and
+//! initramfs images that don't ship `blkid`.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::paths::Paths;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsType {
+    Iso9660,
+    Fat,
+    Ext,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    pub dev: String,
+    pub fstype: FsType,
+    pub label: Option<String>,
+    pub uuid: Option<String>,
+}
+
+const ISO9660_SECTOR_OFFSET: u64 = 0x8000;
+const ISO9660_IDENTIFIER_OFFSET: usize = 1;
+const ISO9660_IDENTIFIER: &[u8] = b"CD001";
+const ISO9660_LABEL_OFFSET: usize = 40;
+const ISO9660_LABEL_LEN: usize = 32;
+
+const FAT1216_TYPE_OFFSET: usize = 0x36;
+const FAT1216_LABEL_OFFSET: usize = 0x2B;
+const FAT32_TYPE_OFFSET: usize = 0x52;
+const FAT32_LABEL_OFFSET: usize = 0x47;
+const FAT_LABEL_LEN: usize = 11;
+
+const EXT_SUPERBLOCK_OFFSET: u64 = 1024;
+const EXT_MAGIC_OFFSET: usize = 56;
+const EXT_MAGIC: u16 = 0xEF53;
+const EXT_UUID_OFFSET: usize = 104;
+const EXT_UUID_LEN: usize = 16;
+const EXT_LABEL_OFFSET: usize = 120;
+const EXT_LABEL_LEN: usize = 16;
+
+/// probe every device under `paths.sys_class_block` and return the ones
+/// recognized as ISO9660, FAT, or ext2/3/4. Devices that don't open, or
+/// whose first few KiB match none of those signatures, are silently skipped;
+/// other filesystem types are not recognized.
+pub fn probe_devices(paths: &Paths) -> Vec<DeviceInfo> {
+    list_block_devices(paths)
+        .into_iter()
+        .filter_map(|dev| probe_device(&paths.dev.join(&dev), &dev))
+        .collect()
+}
+
+fn list_block_devices(paths: &Paths) -> Vec<String> {
+    let Ok(entries) = paths.sys_class_block.read_dir() else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
+
+fn probe_device(path: &Path, dev: &str) -> Option<DeviceInfo> {
+    let mut file = File::open(path).ok()?;
+
+    if let Some(label) = probe_iso9660(&mut file) {
+        return Some(DeviceInfo {
+            dev: dev.to_string(),
+            fstype: FsType::Iso9660,
+            label,
+            uuid: None,
+        });
+    }
+
+    if let Some(label) = probe_fat(&mut file) {
+        return Some(DeviceInfo {
+            dev: dev.to_string(),
+            fstype: FsType::Fat,
+            label,
+            uuid: None,
+        });
+    }
+
+    if let Some((label, uuid)) = probe_ext(&mut file) {
+        return Some(DeviceInfo {
+            dev: dev.to_string(),
+            fstype: FsType::Ext,
+            label,
+            uuid: Some(uuid),
+        });
+    }
+
+    None
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> Option<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
+
+/// the primary volume descriptor at logical sector 16 (byte 0x8000): `CD001`
+/// at offset +1 identifies it, the 32-byte space-padded label follows at
+/// offset +40.
+fn probe_iso9660(file: &mut File) -> Option<Option<String>> {
+    let sector = read_at(
+        file,
+        ISO9660_SECTOR_OFFSET,
+        ISO9660_LABEL_OFFSET + ISO9660_LABEL_LEN,
+    )?;
+    let identifier = sector.get(ISO9660_IDENTIFIER_OFFSET..ISO9660_IDENTIFIER_OFFSET + 5)?;
+    if identifier != ISO9660_IDENTIFIER {
+        return None;
+    }
+    let label = sector.get(ISO9660_LABEL_OFFSET..ISO9660_LABEL_OFFSET + ISO9660_LABEL_LEN)?;
+    Some(trimmed_ascii_label(label, b' '))
+}
+
+/// the boot sector: the 8-byte filesystem-type string at 0x36 (FAT12/16) or
+/// 0x52 (FAT32) confirms the format, and the 11-byte volume label sits at
+/// 0x2B or 0x47 respectively.
+fn probe_fat(file: &mut File) -> Option<Option<String>> {
+    let sector = read_at(file, 0, FAT32_LABEL_OFFSET + FAT_LABEL_LEN)?;
+
+    let fat1216_type = sector.get(FAT1216_TYPE_OFFSET..FAT1216_TYPE_OFFSET + 5)?;
+    if fat1216_type == b"FAT12" || fat1216_type == b"FAT16" {
+        let label = sector.get(FAT1216_LABEL_OFFSET..FAT1216_LABEL_OFFSET + FAT_LABEL_LEN)?;
+        return Some(trimmed_ascii_label(label, b' '));
+    }
+
+    let fat32_type = sector.get(FAT32_TYPE_OFFSET..FAT32_TYPE_OFFSET + 5)?;
+    if fat32_type == b"FAT32" {
+        let label = sector.get(FAT32_LABEL_OFFSET..FAT32_LABEL_OFFSET + FAT_LABEL_LEN)?;
+        return Some(trimmed_ascii_label(label, b' '));
+    }
+
+    None
+}
+
+/// the superblock at byte 1024: the little-endian magic `0xEF53` at
+/// superblock offset 56 confirms ext2/3/4, the 16-byte UUID follows at
+/// offset 104 and the 16-byte null-padded label at offset 120.
+fn probe_ext(file: &mut File) -> Option<(Option<String>, String)> {
+    let superblock = read_at(
+        file,
+        EXT_SUPERBLOCK_OFFSET,
+        EXT_LABEL_OFFSET + EXT_LABEL_LEN,
+    )?;
+
+    let magic_bytes = superblock.get(EXT_MAGIC_OFFSET..EXT_MAGIC_OFFSET + 2)?;
+    let magic = u16::from_le_bytes(magic_bytes.try_into().ok()?);
+    if magic != EXT_MAGIC {
+        return None;
+    }
+
+    let uuid_bytes = superblock.get(EXT_UUID_OFFSET..EXT_UUID_OFFSET + EXT_UUID_LEN)?;
+    let uuid = format_uuid(uuid_bytes);
+
+    let label = superblock.get(EXT_LABEL_OFFSET..EXT_LABEL_OFFSET + EXT_LABEL_LEN)?;
+    Some((trimmed_ascii_label(label, b'\0'), uuid))
+}
+
+fn trimmed_ascii_label(raw: &[u8], pad: u8) -> Option<String> {
+    let trimmed = raw
+        .iter()
+        .rposition(|&b| b != pad && b != 0)
+        .map(|end| &raw[..=end])
+        .unwrap_or(&[]);
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(trimmed).into_owned())
+}
+
+fn format_uuid(bytes: &[u8]) -> String {
+    format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        bytes[0], bytes[1], bytes[2], bytes[3],
+        bytes[4], bytes[5],
+        bytes[6], bytes[7],
+        bytes[8], bytes[9],
+        bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trimmed_ascii_label_strips_padding() {
+        assert_eq!(Some("NoCloud".to_string()), trimmed_ascii_label(b"NoCloud                         ", b' '));
+        assert_eq!(Some("root".to_string()), trimmed_ascii_label(b"root\0\0\0\0\0\0\0\0\0\0\0\0", b'\0'));
+        assert_eq!(None, trimmed_ascii_label(b"                                ", b' '));
+    }
+
+    #[test]
+    fn test_format_uuid() {
+        let bytes: [u8; 16] = [
+            0x4d, 0x3b, 0x1a, 0x2c, 0x9e, 0x7f, 0x4a, 0x11, 0x8c, 0x20, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ];
+        assert_eq!("4d3b1a2c-9e7f-4a11-8c20-aabbccddeeff", format_uuid(&bytes));
+    }
+
+    #[test]
+    fn test_probe_iso9660_recognizes_identifier_and_label() {
+        let mut data = vec![0u8; ISO9660_LABEL_OFFSET + ISO9660_LABEL_LEN];
+        data[ISO9660_IDENTIFIER_OFFSET..ISO9660_IDENTIFIER_OFFSET + 5]
+            .copy_from_slice(ISO9660_IDENTIFIER);
+        data[ISO9660_LABEL_OFFSET..ISO9660_LABEL_OFFSET + 8].copy_from_slice(b"config-2");
+        for b in &mut data[ISO9660_LABEL_OFFSET + 8..ISO9660_LABEL_OFFSET + ISO9660_LABEL_LEN] {
+            *b = b' ';
+        }
+
+        let dir = std::env::temp_dir().join(format!(
+            "ds-identify-fsprobe-test-iso-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dev_path = dir.join("iso.img");
+        std::fs::write(&dev_path, vec![0u8; ISO9660_SECTOR_OFFSET as usize]).unwrap();
+        {
+            use std::io::Write;
+            let mut f = std::fs::OpenOptions::new().write(true).open(&dev_path).unwrap();
+            f.seek(SeekFrom::Start(ISO9660_SECTOR_OFFSET)).unwrap();
+            f.write_all(&data).unwrap();
+        }
+
+        let mut file = File::open(&dev_path).unwrap();
+        assert_eq!(Some(Some("config-2".to_string())), probe_iso9660(&mut file));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,9 +1,12 @@
-use std::{fmt::Display, fs, path::Path};
+use std::fmt::Display;
 
 use crate::{
+    config::Config as YamlConfig,
     constants::{DI_DISABLED, DI_ENABLED},
+    context::Context,
+    logging::Logger,
     paths::Paths,
-    util::unquote,
+    util::{json_opt_string, json_string},
 };
 
 use super::uname::UnameInfo;
@@ -14,6 +17,20 @@ pub struct Config {
     pub on_found: Found,
     pub on_maybe: Maybe,
     pub on_notfound: NotFound,
+    report: Report,
+}
+
+/// bookkeeping for [`Config::render_report`]: which file or kernel-cmdline
+/// token contributed `dsname`/`policy`, and the uname-derived default that
+/// would have applied otherwise.
+#[derive(Debug, Default)]
+struct Report {
+    dsname_source: Option<String>,
+    policy_source: Option<String>,
+    uname_default: String,
+    config_files_considered: Vec<String>,
+    cmdline_tokens: Vec<String>,
+    report_requested: bool,
 }
 
 impl Config {
@@ -28,46 +45,52 @@ impl Config {
         }
     }
 
-    fn from_file(path: &Path) -> (Option<String>, Option<String>) {
-        // TODO: input with explicit keyname
-        if !path.is_file() {
-            panic!("{path:?} exists but is not a file!");
-            // TODO: exit_code 1
-        }
-        let mut dsname = None;
-        let mut policy = None;
-        for line in fs::read_to_string(path).unwrap().lines() {
-            let (key, val) = match line.split_once(':') {
-                None => continue, // no `:` in the line.
-                Some((key, val)) => {
-                    let key = key.trim();
-                    let val = unquote(val.trim());
-                    (key, val)
-                }
-            };
-            match key {
-                "datasource" => dsname = Some(val.to_string()),
-                "policy" => policy = Some(val.to_string()),
-                _ => (),
-            };
-        }
+    pub fn read(
+        ctx: &dyn Context,
+        logger: &Logger,
+        paths: &Paths,
+        kernel_cmdline: &str,
+        uname: &UnameInfo,
+    ) -> Self {
+        let mut report = Report {
+            uname_default: Policy::describe_uname_default(uname),
+            ..Report::default()
+        };
 
-        (dsname, policy)
-    }
+        // ds-identify.cfg, then its ds-identify.cfg.d/*.cfg drop-ins, in
+        // lexical order; deep-merged by `YamlConfig` so a drop-in that only
+        // sets one of `datasource`/`policy` doesn't clobber the other from
+        // an earlier file.
+        let cfg_paths = paths.di_config_paths(logger);
+        report.config_files_considered = cfg_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        let yaml_config = YamlConfig::read(ctx, logger, &cfg_paths);
 
-    pub fn read(paths: &Paths, kernel_cmdline: &str, uname: &UnameInfo) -> Self {
-        let mut dsname = None;
-        let mut policy = None;
-        if paths.di_config.exists() {
-            (dsname, policy) = Self::from_file(&paths.di_config);
-        };
+        let mut dsname = yaml_config.dsname().map(|(name, path)| {
+            report.dsname_source = Some(path.display().to_string());
+            name
+        });
+        let mut policy = yaml_config.policy().map(|(policy, path)| {
+            report.policy_source = Some(path.display().to_string());
+            policy
+        });
 
         for tok in kernel_cmdline.split(' ') {
             match tok.split_once('=') {
                 None => continue,
                 Some((key, val)) => match key {
-                    "ci.ds" | "ci.datasource" => dsname = Some(val.to_string()),
-                    "ci.di.policy" => policy = Some(val.to_string()),
+                    "ci.ds" | "ci.datasource" => {
+                        dsname = Some(val.to_string());
+                        report.dsname_source = Some(format!("kernel cmdline: {tok}"));
+                        report.cmdline_tokens.push(tok.to_string());
+                    }
+                    "ci.di.policy" => {
+                        policy = Some(val.to_string());
+                        report.policy_source = Some(format!("kernel cmdline: {tok}"));
+                        report.cmdline_tokens.push(tok.to_string());
+                    }
                     _ => continue,
                 },
             }
@@ -77,9 +100,7 @@ impl Config {
             Some(p) => Policy::parse_from_str(&p, uname),
             None => Policy::parse_from_uname(uname),
         };
-
-        // TODO: `debug` policy
-        dbg!(&policy);
+        report.report_requested = policy.report;
 
         Self {
             dsname,
@@ -87,8 +108,46 @@ impl Config {
             on_found: policy.on_found,
             on_maybe: policy.on_maybe,
             on_notfound: policy.on_notfound,
+            report,
         }
     }
+
+    /// a JSON dump of the resolved configuration and why it was chosen:
+    /// which file or kernel-cmdline token contributed `dsname`/`policy`,
+    /// and the uname-derived default policy that would otherwise apply.
+    /// Used by `Mode::Report` so downstream tooling can introspect a
+    /// datasource decision.
+    pub fn render_report(&self) -> String {
+        let config_files_considered = self
+            .report
+            .config_files_considered
+            .iter()
+            .map(|f| json_string(f))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let cmdline_tokens = self
+            .report
+            .cmdline_tokens
+            .iter()
+            .map(|t| json_string(t))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "{{\"dsname\":{},\"dsname_source\":{},\"policy_source\":{},\"mode\":{},\"on_found\":{},\"on_maybe\":{},\"on_notfound\":{},\"report_requested\":{},\"uname_default\":{},\"config_files_considered\":[{}],\"kernel_cmdline_tokens\":[{}]}}",
+            json_opt_string(self.dsname.as_deref()),
+            json_opt_string(self.report.dsname_source.as_deref()),
+            json_opt_string(self.report.policy_source.as_deref()),
+            json_string(&self.mode.to_string()),
+            json_string(&self.on_found.cli_repr()),
+            json_string(&self.on_maybe.cli_repr()),
+            json_string(&self.on_notfound.cli_repr()),
+            self.report.report_requested,
+            json_string(&self.report.uname_default),
+            config_files_considered,
+            cmdline_tokens,
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -173,7 +232,9 @@ struct Policy {
     on_found: Found,
     on_maybe: Maybe,
     on_notfound: NotFound,
-    _report: bool,
+    /// whether `report` was explicitly requested (by policy token or
+    /// `ci.di.policy=report`), surfaced through [`Config::render_report`].
+    report: bool,
 }
 
 impl Default for Policy {
@@ -183,7 +244,7 @@ impl Default for Policy {
             on_found: Found::default(),
             on_maybe: Maybe::default(),
             on_notfound: NotFound::default(),
-            _report: false,
+            report: false,
         }
     }
 }
@@ -202,7 +263,7 @@ impl Policy {
     // XXX: impl From trait
     fn parse_from_uname(uname: &UnameInfo) -> Self {
         #[allow(clippy::wildcard_in_or_patterns)]
-        match &uname.machine[..] {
+        match uname.machine.to_string_lossy().as_ref() {
             // these have dmi data
             "i686" | "i386" | "x86_64" => Policy::default(),
             // aarch64 has dmi, but not currently used (LP: #1663304)
@@ -210,6 +271,19 @@ impl Policy {
         }
     }
 
+    /// describes the uname-derived default policy for [`Config::render_report`],
+    /// independent of whatever `policy`/`ci.di.policy` override is applied on top.
+    fn describe_uname_default(uname: &UnameInfo) -> String {
+        let machine = uname.machine.to_string_lossy();
+        #[allow(clippy::wildcard_in_or_patterns)]
+        match machine.as_ref() {
+            "i686" | "i386" | "x86_64" => {
+                format!("machine={machine}: has dmi data, using default policy")
+            }
+            "aarch64" | _ => format!("machine={machine}: no dmi data, using default_no_dmi policy"),
+        }
+    }
+
     fn parse_from_str(policy_str: &str, uname: &UnameInfo) -> Self {
         let mut policy = Policy::parse_from_uname(uname);
 
@@ -258,6 +332,8 @@ impl Policy {
             policy.on_notfound = x;
         };
 
+        policy.report = matches!(policy.mode, Mode::Report);
+
         policy
     }
 
@@ -265,3 +341,36 @@ impl Policy {
         eprintln!("WARN: invalid value '{invalid}' for key '{key}'. Using {key}={valid}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_report_json_shape() {
+        let config = Config {
+            dsname: Some("NoCloud".to_string()),
+            mode: Mode::Report,
+            on_found: Found::All,
+            on_maybe: Maybe::All,
+            on_notfound: NotFound::Disabled,
+            report: Report {
+                dsname_source: Some("/etc/cloud/ds-identify.cfg".to_string()),
+                policy_source: None,
+                uname_default: "machine=x86_64: has dmi data, using default policy".to_string(),
+                config_files_considered: vec!["/etc/cloud/ds-identify.cfg".to_string()],
+                cmdline_tokens: vec![],
+                report_requested: true,
+            },
+        };
+
+        let report = config.render_report();
+        assert!(report.contains("\"dsname\":\"NoCloud\""));
+        assert!(report.contains("\"dsname_source\":\"/etc/cloud/ds-identify.cfg\""));
+        assert!(report.contains("\"policy_source\":null"));
+        assert!(report.contains("\"mode\":\"report\""));
+        assert!(report.contains("\"report_requested\":true"));
+        assert!(report.contains("\"config_files_considered\":[\"/etc/cloud/ds-identify.cfg\"]"));
+        assert!(report.contains("\"kernel_cmdline_tokens\":[]"));
+    }
+}
@@ -1,14 +1,15 @@
-use ::std::process;
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStrExt;
+use std::process;
 
 #[derive(Debug)]
 pub struct UnameInfo {
-    pub kernel_name: String,
-    pub node_name: String,
-    pub kernel_release: String,
-    pub kernel_version: String,
-    pub machine: String,
-    pub operating_system: String,
-    _cmd_out: String,
+    pub kernel_name: OsString,
+    pub node_name: OsString,
+    pub kernel_release: OsString,
+    pub kernel_version: OsString,
+    pub machine: OsString,
+    pub operating_system: OsString,
 }
 
 impl UnameInfo {
@@ -28,16 +29,19 @@ impl UnameInfo {
             .arg("-snrvmo")
             .output()
             .expect(ERR_MSG);
-        let out = String::from_utf8(output.stdout).expect(ERR_MSG);
 
-        let mut out_words = out.split(' ');
+        // nodename and kernel-version are free-form and not guaranteed to be
+        // valid UTF-8, so split on the raw bytes rather than decoding the
+        // whole line up front.
+        let out = output.stdout;
+        let mut out_words = out.split(|&b| b == b' ');
 
-        let kernel_name = out_words.next().unwrap().to_string();
-        let node_name = out_words.next().unwrap().to_string();
-        let kernel_release = out_words.next().unwrap().to_string();
-        let operating_system = out_words.next_back().unwrap().to_string();
-        let machine = out_words.next_back().unwrap().to_string();
-        let kernel_version = out_words.collect::<Vec<_>>().join(" ");
+        let kernel_name = os_string_from_bytes(out_words.next().expect(ERR_MSG));
+        let node_name = os_string_from_bytes(out_words.next().expect(ERR_MSG));
+        let kernel_release = os_string_from_bytes(out_words.next().expect(ERR_MSG));
+        let operating_system = os_string_from_bytes(out_words.next_back().expect(ERR_MSG));
+        let machine = os_string_from_bytes(out_words.next_back().expect(ERR_MSG));
+        let kernel_version = os_string_from_bytes(&out_words.collect::<Vec<_>>().join(&b' '));
 
         UnameInfo {
             kernel_name,
@@ -46,7 +50,10 @@ impl UnameInfo {
             kernel_version,
             machine,
             operating_system,
-            _cmd_out: out,
         }
     }
 }
+
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    std::ffi::OsStr::from_bytes(bytes).to_os_string()
+}
@@ -0,0 +1,174 @@
+//! `Logger` is the `log::Log` backend ds-identify installs for its own
+//! diagnostics. It replaces a hand-rolled integer debug gate with
+//! `log::LevelFilter`, and a single fixed file writer with a [`Sink`] chosen
+//! from the `DI_LOG` value: a file (the original behavior, with parent-dir
+//! creation), `stderr`, or `syslog`/`journald` so ds-identify's boot-time
+//! diagnostics can land in the system journal like everything else on the box.
+
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::error::DsIdentifyError;
+use crate::util::get_env_var;
+
+/// where formatted log lines end up, chosen from the `DI_LOG` value.
+enum Sink {
+    File(Mutex<BufWriter<fs::File>>),
+    Stderr,
+    Syslog(UnixDatagram),
+}
+
+impl Sink {
+    fn from_di_log(di_log: &str) -> Result<Self, DsIdentifyError> {
+        match di_log.trim() {
+            "stderr" => Ok(Self::Stderr),
+            "syslog" | "journald" => {
+                let socket = UnixDatagram::unbound()
+                    .and_then(|socket| {
+                        socket.connect("/dev/log")?;
+                        Ok(socket)
+                    })
+                    .map_err(|e| DsIdentifyError::log_init(di_log, e))?;
+                Ok(Self::Syslog(socket))
+            }
+            log_file => {
+                if let Some(parent_dir) = Path::new(log_file).parent() {
+                    fs::create_dir_all(parent_dir)
+                        .map_err(|e| DsIdentifyError::log_init(log_file, e))?;
+                }
+                let file = fs::OpenOptions::new()
+                    .append(true)
+                    .create(true)
+                    .open(log_file)
+                    .map_err(|e| DsIdentifyError::log_init(log_file, e))?;
+                Ok(Self::File(Mutex::new(BufWriter::new(file))))
+            }
+        }
+    }
+
+    fn write(&self, level: Level, line: &str) {
+        match self {
+            Self::File(writer) => {
+                if let Ok(mut writer) = writer.lock() {
+                    let _ = writeln!(writer, "{line}");
+                }
+            }
+            Self::Stderr => eprintln!("{line}"),
+            Self::Syslog(socket) => {
+                // facility "daemon" (3), severity from the log level. No
+                // timestamp/hostname/PID: the syslog daemon listening on
+                // `/dev/log` fills those in for datagrams that arrive without them.
+                let severity = match level {
+                    Level::Error => 3,
+                    Level::Warn => 4,
+                    Level::Info => 6,
+                    Level::Debug | Level::Trace => 7,
+                };
+                let packet = format!("<{}>ds-identify: {line}", 3 * 8 + severity);
+                let _ = socket.send(packet.as_bytes());
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Self::File(writer) = self {
+            if let Ok(mut writer) = writer.lock() {
+                let _ = writer.flush();
+            }
+        }
+    }
+}
+
+pub struct Logger {
+    level: LevelFilter,
+    sink: Sink,
+}
+
+impl Logger {
+    /// builds the logger and installs it as the process-wide `log` backend,
+    /// so `RUST_LOG` filtering and the `log` crate's macros work from
+    /// anywhere in the crate, not just through the `debug`/`warn`/`error`
+    /// methods below. Leaked rather than returned by value: `log::set_logger`
+    /// requires a `'static` reference, and every call site already only
+    /// ever holds `logger` for the lifetime of the process.
+    pub fn new<P: AsRef<Path>>(di_log: P) -> Result<&'static Self, DsIdentifyError> {
+        let logger = Self {
+            level: Self::level_from_env(),
+            sink: Sink::from_di_log(&di_log.as_ref().to_string_lossy())?,
+        };
+        let logger: &'static Self = Box::leak(Box::new(logger));
+        log::set_max_level(logger.level);
+        let _ = log::set_logger(logger);
+        Ok(logger)
+    }
+
+    /// `RUST_LOG` wins when it parses as a `LevelFilter`, so this logger can
+    /// be filtered the same way as anything else built on the `log` facade.
+    /// Otherwise fall back to ds-identify's own `DEBUG_LEVEL` integer, whose
+    /// default of `-1` means "log everything" and whose 1/2 tiers are this
+    /// crate's existing debug verbosity levels.
+    fn level_from_env() -> LevelFilter {
+        if let Ok(level) = get_env_var("RUST_LOG", String::new()).parse() {
+            return level;
+        }
+        match get_env_var("DEBUG_LEVEL", String::from("-1")).parse::<i32>() {
+            Ok(0) => LevelFilter::Warn,
+            Ok(1) => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    /// whether this logger's sink is `stderr`, so callers that need to avoid
+    /// colliding with it (e.g. printing the collected info to stdout) can
+    /// ask the logger instead of re-deriving the answer from `DI_LOG`.
+    pub fn prints_to_stderr(&self) -> bool {
+        matches!(self.sink, Sink::Stderr)
+    }
+
+    pub fn debug<S: AsRef<str>>(&self, level: i32, msg: S) {
+        let level = if level >= 2 { Level::Trace } else { Level::Debug };
+        self.emit(level, msg.as_ref());
+    }
+
+    pub fn warn<S: AsRef<str>>(&self, msg: S) {
+        self.emit(Level::Warn, msg.as_ref());
+    }
+
+    pub fn error<S: AsRef<str>>(&self, msg: S) {
+        self.emit(Level::Error, msg.as_ref());
+    }
+
+    fn emit(&self, level: Level, msg: &str) {
+        Log::log(
+            self,
+            &Record::builder()
+                .level(level)
+                .target("ds_identify")
+                .args(format_args!("{msg}"))
+                .build(),
+        );
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!("{}: {}", record.level(), record.args());
+        self.sink.write(record.level(), &line);
+    }
+
+    fn flush(&self) {
+        self.sink.flush();
+    }
+}
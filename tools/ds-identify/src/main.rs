@@ -1,13 +1,21 @@
 use std::io::{BufRead, BufReader, BufWriter, Write};
 
 use ds_identify::constants::UNAVAILABLE;
-use ds_identify::dss::{Datasource, DscheckResult};
-use ds_identify::info::{DatasourceList, Found, Info, Maybe, Mode, NotFound};
+use ds_identify::dss::DscheckResult;
+use ds_identify::error::DsIdentifyError;
+use ds_identify::context::SystemContext;
+use ds_identify::info::{Found, Info, Maybe, Mode, NotFound};
+use ds_identify::logging::Logger;
 use ds_identify::paths::Paths;
-use ds_identify::util::{ensure_sane_path, get_env_var, Logger};
+use ds_identify::sources::DatasourceList;
+use ds_identify::util::{ensure_sane_path, get_env_var};
 use std::process::ExitCode;
 use std::{env, fs, path::Path};
 
+/// exit code returned when ds-identify could not complete detection at all
+/// (as opposed to completing it and deciding cloud-init should stay disabled).
+const RET_ERROR: u8 = 3;
+
 fn read_uptime<P: AsRef<Path>>(path: P) -> String {
     let res = String::from(UNAVAILABLE);
     let file = match fs::File::open(path) {
@@ -28,32 +36,30 @@ fn is_manual_clean_and_exiting(var_lib_cloud: &Path) -> bool {
     var_lib_cloud.join("instance/manual-clean").is_file()
 }
 
-fn write_result(logger: &Logger, content: &str, paths: &Paths, mode: &Mode) {
+fn write_result(
+    logger: &Logger,
+    content: &str,
+    paths: &Paths,
+    mode: &Mode,
+) -> Result<(), DsIdentifyError> {
     let runcfg = &paths.run_ci_cfg;
-    let error_fn = || {
+    let file = fs::File::create(runcfg).map_err(|e| {
         logger.error(format!("failed to write to {:?}", runcfg));
-        panic!("failed to write to {:?}", runcfg);
-    };
-
-    let file = fs::File::create(&paths.run_ci_cfg);
-    let mut ostream = match file {
-        Err(e) => {
-            eprintln!("{}", e);
-            error_fn()
-        }
-        Ok(file) => BufWriter::new(file),
-    };
+        DsIdentifyError::io(runcfg.clone(), e)
+    })?;
+    let mut ostream = BufWriter::new(file);
 
     let pre = match mode {
         Mode::Report => "  ",
         _ => "",
     };
     for line in content.lines() {
-        if line.len() == 0 {
+        if line.is_empty() {
             continue;
         }
-        writeln!(ostream, "{}{}", pre, line).unwrap();
+        writeln!(ostream, "{}{}", pre, line).map_err(|e| DsIdentifyError::io(runcfg.clone(), e))?;
     }
+    Ok(())
 }
 
 fn found<S: AsRef<str>>(
@@ -61,8 +67,8 @@ fn found<S: AsRef<str>>(
     mode: Option<&Mode>,
     ds_list: &[S],
     extra_lines: Option<&str>,
-) {
-    let mode = mode.unwrap_or_else(|| &info.config().mode);
+) -> Result<(), DsIdentifyError> {
+    let mode = mode.unwrap_or_else(|| info.config().mode());
 
     let list = ds_list
         .iter()
@@ -71,47 +77,46 @@ fn found<S: AsRef<str>>(
         .join(", ");
     // TODO: Add ds None as fallback
     let result = format!("datasource_list: [{}]", list);
-    write_result(&info.logger(), &result, &info.paths(), mode);
+    write_result(info.logger(), &result, info.paths(), mode)?;
     if let Some(extra_lines) = extra_lines {
-        write_result(&info.logger(), &extra_lines, &info.paths(), mode);
+        write_result(info.logger(), extra_lines, info.paths(), mode)?;
     }
+    Ok(())
 }
 
 /// in report mode, report nothing was found.
 /// if not report mode: only report the negative result.
 ///   reporting an empty list would mean cloud-init would not search
 ///   any datasources.
-fn record_notfound(info: &Info) {
+fn record_notfound(info: &Info) -> Result<(), DsIdentifyError> {
     match info.config().mode() {
-        Mode::Report => {
-            found::<&str>(&info, None, &[], None);
-        }
+        Mode::Report => found::<&str>(info, None, &[], None),
         Mode::Search => {
             let msg = format!(
                 "# reporting not found result. notfound={}.",
                 info.config().on_notfound.cli_repr()
             );
-            found::<&str>(&info, Some(&Mode::Report), &[], Some(&msg));
+            found::<&str>(info, Some(&Mode::Report), &[], Some(&msg))
         }
-        _ => (),
+        _ => Ok(()),
     }
 }
 
-fn print_info() {
-    let paths = Paths::from_env();
-    let logger = Logger::new(&paths.log());
-    let info = Info::collect_info(&logger, &paths);
+fn print_info() -> Result<(), DsIdentifyError> {
+    let ctx = SystemContext;
+    let paths = Paths::from_env(&ctx);
+    paths.resolve()?;
+    let logger = Logger::new(paths.log())?;
+    let info = Info::collect_info(logger, &ctx, &paths)?;
     println!("{}", info.to_old_str());
+    Ok(())
 }
 
-fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
-    let di_log = info.paths().log();
-    if di_log == "stderr" {
-        todo!();
+fn ds_identify_inner(logger: &Logger, info: &Info) -> Result<u8, DsIdentifyError> {
+    if logger.prints_to_stderr() {
+        eprintln!("{}", info.to_old_str());
     } else {
-        let old_cli_str = info.to_old_str();
-        // TODO: print to `DI_LOG`;
-        println!("{}", old_cli_str);
+        println!("{}", info.to_old_str());
     }
 
     const RET_DISABLED: u8 = 1;
@@ -123,22 +128,32 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
                 1,
                 format!("mode={}. returning {}", Mode::Disabled, RET_DISABLED),
             );
-            return RET_DISABLED;
+            return Ok(RET_DISABLED);
         }
         Mode::Enabled => {
             logger.debug(
                 1,
                 format!("mode={}. returning {}", Mode::Enabled, RET_ENABLED),
             );
-            return RET_ENABLED;
+            return Ok(RET_ENABLED);
         }
         _ => (),
     }
 
+    if matches!(info.config().mode(), Mode::Report) {
+        let report = info.config().render_report();
+        logger.debug(1, format!("config report: {report}"));
+        if logger.prints_to_stderr() {
+            eprintln!("{report}");
+        } else {
+            println!("{report}");
+        }
+    }
+
     if let Some(dsname) = info.config().dsname() {
         logger.debug(1, format!("datasource '{dsname}' specified."));
-        found(&info, None, &[dsname], None);
-        return 0;
+        found(info, None, &[dsname], None)?;
+        return Ok(0);
     }
 
     if is_manual_clean_and_exiting(&info.paths().var_lib_cloud) {
@@ -147,12 +162,12 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
             "manual_cache_clean enabled. Not writing datasource_list.",
         );
         write_result(
-            &logger,
+            logger,
             "# manual_cache_clean.",
             info.paths(),
             info.config().mode(),
-        );
-        return 0;
+        )?;
+        return Ok(0);
     }
 
     // if there is only a single entry in $DI_DSLIST
@@ -165,8 +180,8 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
             ),
         );
         let ds_list = info.dslist().as_old_list();
-        found(&info, None, &ds_list, None);
-        return 0;
+        found(info, None, &ds_list, None)?;
+        return Ok(0);
     }
 
     // Check datasources
@@ -177,12 +192,12 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
     for ds in info.dslist() {
         let ds_as_str = String::from(ds);
         logger.debug(2, format!("Checking for datasource '{}'", ds_as_str));
-        if let Datasource::Unknown(ds) = ds {
-            logger.warn(format!("No check method for datasource '{}'", ds));
+        let Some(dscheck) = ds.dscheck_fn() else {
+            logger.warn(format!("No check method for datasource '{}'", ds_as_str));
             continue;
-        }
+        };
 
-        match ds.dscheck_fn()(&info) {
+        match dscheck(info) {
             DscheckResult::Found(extra_config) => {
                 logger.debug(1, format!("check for '{}' returned found", ds_as_str));
                 found_dss.push(ds.clone());
@@ -205,7 +220,7 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
 
     logger.debug(2, format!("found={:?} maybe={:?}", found_dss, maybe_dss));
     if found_dss.len() > 0 {
-        let first_ds = found_dss.into_iter().nth(0).expect("at leaset one");
+        let first_ds = found_dss.into_iter().next().expect("at least one");
         if found_dss.len() == 1 {
             logger.debug(
                 1,
@@ -226,8 +241,8 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
                 found_dss.keep_first();
             }
         }
-        found(&info, None, &found_dss.as_old_list(), Some(&exfound));
-        return 0;
+        found(info, None, &found_dss.as_old_list(), Some(&exfound))?;
+        return Ok(0);
     }
 
     if maybe_dss.len() > 0 && !matches!(info.config().on_maybe, Maybe::None) {
@@ -239,12 +254,12 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
                 maybe_dss
             ),
         );
-        found(&info, None, &maybe_dss.as_old_list(), Some(&exmaybe));
-        return 0;
+        found(info, None, &maybe_dss.as_old_list(), Some(&exmaybe))?;
+        return Ok(0);
     }
 
     // record the empty result.
-    record_notfound(&info);
+    record_notfound(info)?;
 
     let base_msg = format!(
         "No ds found [mode={}, notfound={}].",
@@ -271,19 +286,30 @@ fn ds_identify_inner(logger: &Logger, info: &Info) -> u8 {
         }
         _ => {
             logger.error("Unexpected result");
-            (String::from(""), 3)
+            (String::from(""), RET_ERROR)
         }
     };
     logger.debug(1, msg);
-    ret_code
+    Ok(ret_code)
 }
 
 fn ds_identify() -> ExitCode {
     ensure_sane_path();
 
-    let paths = Paths::from_env();
+    let ctx = SystemContext;
+    let paths = Paths::from_env(&ctx);
+    if let Err(e) = paths.resolve() {
+        eprintln!("ERROR: {e}");
+        return ExitCode::from(RET_ERROR);
+    }
     let di_log = paths.log();
-    let logger = Logger::new(&di_log);
+    let logger = match Logger::new(&di_log) {
+        Ok(logger) => logger,
+        Err(e) => {
+            eprintln!("ERROR: {e}");
+            return ExitCode::from(RET_ERROR);
+        }
+    };
 
     let args: Vec<String> = env::args().skip(1).collect();
     let args_str: &str = &args.join(" ");
@@ -297,10 +323,19 @@ fn ds_identify() -> ExitCode {
         ),
     );
 
-    let info = Info::collect_info(&logger, &paths);
+    let info = match Info::collect_info(logger, &ctx, &paths) {
+        Ok(info) => info,
+        Err(e) => {
+            logger.error(format!("failed to collect info: {e}"));
+            return ExitCode::from(RET_ERROR);
+        }
+    };
 
     if !paths.run_ci.is_dir() {
-        fs::create_dir_all(&paths.run_ci).unwrap();
+        if let Err(e) = fs::create_dir_all(&paths.run_ci) {
+            logger.error(format!("failed to create {:?}: {}", paths.run_ci, e));
+            return ExitCode::from(RET_ERROR);
+        }
     }
 
     // Handle cache
@@ -321,7 +356,7 @@ fn ds_identify() -> ExitCode {
                             &previous_code
                         ),
                     );
-                    return ExitCode::from(previous_code.parse::<u8>().expect("valid exit_code"));
+                    return ExitCode::from(previous_code.parse::<u8>().unwrap_or(RET_ERROR));
                 }
                 _ => {
                     logger.debug(
@@ -341,11 +376,24 @@ fn ds_identify() -> ExitCode {
         }
     }
 
-    let ret_code = ds_identify_inner(&logger, &info);
+    let ret_code = match ds_identify_inner(logger, &info) {
+        Ok(ret_code) => ret_code,
+        Err(e) => {
+            logger.error(format!("cause: {e}"));
+            RET_ERROR
+        }
+    };
 
-    let mut result_file =
-        fs::File::create(&info.paths().run_di_result).expect("accessible result file");
-    write!(result_file, "{}", ret_code).expect("result file accessible");
+    if let Err(e) = fs::File::create(&info.paths().run_di_result)
+        .and_then(|mut f| write!(f, "{}", ret_code).map_err(Into::into))
+    {
+        logger.error(format!(
+            "failed to write result to {:?}: {}",
+            info.paths().run_di_result,
+            e
+        ));
+        return ExitCode::from(RET_ERROR);
+    }
 
     logger.debug(
         1,
@@ -363,10 +411,13 @@ fn main() -> ExitCode {
     let di_main = get_env_var("DI_MAIN", String::from("main"));
     match &di_main[..] {
         "main" => ds_identify(),
-        "print_info" => {
-            print_info();
-            ExitCode::SUCCESS
-        }
+        "print_info" => match print_info() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("ERROR: {e}");
+                ExitCode::from(RET_ERROR)
+            }
+        },
         _ => {
             eprintln!("unexpected value for DI_MAIN");
             ExitCode::FAILURE
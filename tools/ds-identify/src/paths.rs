@@ -1,8 +1,11 @@
 use crate::constants::*;
+use crate::context::Context;
+use crate::error::DsIdentifyError;
+use crate::logging::Logger;
 use std::{
-    env,
     ffi::OsStr,
-    path::{Path, PathBuf},
+    io,
+    path::{Component, Path, PathBuf},
 };
 
 #[derive(Debug, Clone)]
@@ -10,8 +13,12 @@ pub struct Paths {
     root: PathBuf,
     pub run: PathBuf,
     pub sys_class_dmi_id: PathBuf,
+    pub sys_class_block: PathBuf,
+    pub sys_firmware_efi: PathBuf,
+    pub dev: PathBuf,
     pub var_lib_cloud: PathBuf,
     pub di_config: PathBuf,
+    pub di_config_d: PathBuf,
     pub proc_cmdline: PathBuf,
     pub proc_1_cmdline: PathBuf,
     pub proc_1_environ: PathBuf,
@@ -28,7 +35,7 @@ impl Paths {
     fn with_root(root: &Path) -> Self {
         let run = Self::compose_paths(root, PATH_RUN);
         let run_ci = Self::compose_paths(&run, PATH_RUN_CI);
-        let etc_cloud = Self::compose_paths(&root, PATH_ETC_CLOUD);
+        let etc_cloud = Self::compose_paths(root, PATH_ETC_CLOUD);
         Self::from_roots(root, &run, &run_ci, &etc_cloud)
     }
 
@@ -39,8 +46,12 @@ impl Paths {
             root: root.to_owned(),
             run: run.to_owned(),
             sys_class_dmi_id: Self::compose_paths(root, PATH_SYS_CLASS_DMI_ID),
+            sys_class_block: Self::compose_paths(root, PATH_SYS_CLASS_BLOCK),
+            sys_firmware_efi: Self::compose_paths(root, PATH_SYS_FIRMWARE_EFI),
+            dev: Self::compose_paths(root, PATH_DEV),
             var_lib_cloud: Self::compose_paths(root, PATH_VAR_LIB_CLOUD),
             di_config: Self::compose_paths(root, PATH_DI_CONFIG),
+            di_config_d: Self::compose_paths(root, format!("{}.d", PATH_DI_CONFIG)),
             proc_cmdline: Self::compose_paths(root, PATH_PROC_CMDLINE),
             proc_1_cmdline: Self::compose_paths(root, PATH_PROC_1_CMDLINE),
             proc_1_environ: Self::compose_paths(root, PATH_PROC_1_ENVIRON),
@@ -62,52 +73,79 @@ impl Paths {
         root.as_ref().join(default.as_ref())
     }
 
-    fn path_from_env<S>(name: &str, root: Option<&Path>, default: S) -> PathBuf
+    fn path_from_env<S>(ctx: &dyn Context, name: &str, root: Option<&Path>, default: S) -> PathBuf
     where
         S: AsRef<OsStr>,
     {
-        match (env::var(name), root) {
-            (Ok(path), _) => PathBuf::from(&path),
-            (_, Some(root)) => Self::compose_paths(&root, default.as_ref()),
+        match (ctx.env(name), root) {
+            (Some(path), _) => PathBuf::from(&path),
+            (_, Some(root)) => Self::compose_paths(root, default.as_ref()),
             (_, None) => PathBuf::from(default.as_ref()),
         }
     }
-    pub fn from_env() -> Self {
-        let root = env::var("PATH_ROOT").unwrap_or_else(|_| String::from("/"));
+    pub fn from_env(ctx: &dyn Context) -> Self {
+        let root = ctx.env("PATH_ROOT").unwrap_or_else(|| String::from("/"));
         let root = Path::new(&root);
-        let run = Self::path_from_env("PATH_RUN", Some(&root), &PATH_RUN);
-        let etc_cloud = Self::path_from_env("PATH_ETC_CLOUD", Some(&root), &PATH_ETC_CLOUD);
-        let run_ci = Self::path_from_env("PATH_RUN_CI", Some(&run), &PATH_RUN_CI);
+        let run = Self::path_from_env(ctx, "PATH_RUN", Some(root), PATH_RUN);
+        let etc_cloud = Self::path_from_env(ctx, "PATH_ETC_CLOUD", Some(root), PATH_ETC_CLOUD);
+        let run_ci = Self::path_from_env(ctx, "PATH_RUN_CI", Some(&run), PATH_RUN_CI);
 
         let default_paths = Paths::from_roots(&root, &run, &run_ci, &etc_cloud);
 
         let sys_class_dmi_id = Self::path_from_env(
+            ctx,
             "PATH_SYS_CLASS_DMI_ID",
             None,
             &default_paths.sys_class_dmi_id,
         );
+        let sys_class_block = Self::path_from_env(
+            ctx,
+            "PATH_SYS_CLASS_BLOCK",
+            None,
+            &default_paths.sys_class_block,
+        );
+        let sys_firmware_efi = Self::path_from_env(
+            ctx,
+            "PATH_SYS_FIRMWARE_EFI",
+            None,
+            &default_paths.sys_firmware_efi,
+        );
+        let dev = Self::path_from_env(ctx, "PATH_DEV", None, &default_paths.dev);
         let var_lib_cloud =
-            Self::path_from_env("PATH_VAR_LIB_CLOUD", None, &default_paths.var_lib_cloud);
-        let di_config = Self::path_from_env("PATH_DI_CONFIG", None, &default_paths.di_config);
+            Self::path_from_env(ctx, "PATH_VAR_LIB_CLOUD", None, &default_paths.var_lib_cloud);
+        let di_config = Self::path_from_env(ctx, "PATH_DI_CONFIG", None, &default_paths.di_config);
+        let di_config_d =
+            Self::path_from_env(ctx, "PATH_DI_CONFIG_D", None, &default_paths.di_config_d);
         let proc_cmdline =
-            Self::path_from_env("PATH_PROC_CMDLINE", None, &default_paths.proc_cmdline);
+            Self::path_from_env(ctx, "PATH_PROC_CMDLINE", None, &default_paths.proc_cmdline);
         let proc_1_cmdline =
-            Self::path_from_env("PATH_PROC_1_CMDLINE", None, &default_paths.proc_1_cmdline);
+            Self::path_from_env(ctx, "PATH_PROC_1_CMDLINE", None, &default_paths.proc_1_cmdline);
         let proc_1_environ =
-            Self::path_from_env("PATH_PROC_1_ENVIRON", None, &default_paths.proc_1_environ);
-        let proc_uptime = Self::path_from_env("PATH_PROC_UPTIME", None, &default_paths.proc_uptime);
-        let etc_ci_cfg = Self::path_from_env("PATH_ETC_CI_CFG", None, &default_paths.etc_ci_cfg);
+            Self::path_from_env(ctx, "PATH_PROC_1_ENVIRON", None, &default_paths.proc_1_environ);
+        let proc_uptime =
+            Self::path_from_env(ctx, "PATH_PROC_UPTIME", None, &default_paths.proc_uptime);
+        let etc_ci_cfg =
+            Self::path_from_env(ctx, "PATH_ETC_CI_CFG", None, &default_paths.etc_ci_cfg);
         let etc_ci_cfg_d =
-            Self::path_from_env("PATH_ETC_CI_CFG_D", None, &default_paths.etc_ci_cfg_d);
-        let run_ci_cfg = Self::path_from_env("PATH_RUN_CI_CFG", None, &default_paths.run_ci_cfg);
-        let run_di_result =
-            Self::path_from_env("PATH_RUN_DI_RESULT", None, &default_paths.run_di_result);
+            Self::path_from_env(ctx, "PATH_ETC_CI_CFG_D", None, &default_paths.etc_ci_cfg_d);
+        let run_ci_cfg =
+            Self::path_from_env(ctx, "PATH_RUN_CI_CFG", None, &default_paths.run_ci_cfg);
+        let run_di_result = Self::path_from_env(
+            ctx,
+            "PATH_RUN_DI_RESULT",
+            None,
+            &default_paths.run_di_result,
+        );
 
         Paths {
             root: PathBuf::from(root),
             sys_class_dmi_id,
+            sys_class_block,
+            sys_firmware_efi,
+            dev,
             var_lib_cloud,
             di_config,
+            di_config_d,
             run,
             proc_cmdline,
             proc_1_cmdline,
@@ -127,19 +165,178 @@ impl Paths {
         self.run_ci.join("ds-identify.log")
     }
 
-    pub fn etc_ci_cfg_paths(&self) -> Vec<PathBuf> {
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn etc_ci_cfg_paths(&self, logger: &Logger) -> Vec<PathBuf> {
         let mut cfg_paths = vec![self.etc_ci_cfg.clone()];
 
         if self.etc_ci_cfg.is_dir() {
-            for entry in self.etc_ci_cfg_d.read_dir().unwrap() {
-                let entry = entry.unwrap().path();
-                if !entry.ends_with(".cfg") {
-                    continue;
+            match self.etc_ci_cfg_d.read_dir() {
+                Err(e) => logger.warn(format!(
+                    "failed to read {:?}: {}. skipping drop-ins.",
+                    self.etc_ci_cfg_d, e
+                )),
+                Ok(entries) => {
+                    for entry in entries {
+                        let entry = match entry {
+                            Ok(entry) => entry.path(),
+                            Err(e) => {
+                                logger.warn(format!(
+                                    "failed to read an entry of {:?}: {}. skipping it.",
+                                    self.etc_ci_cfg_d, e
+                                ));
+                                continue;
+                            }
+                        };
+                        if !entry.extension().is_some_and(|ext| ext == "cfg") {
+                            continue;
+                        }
+                        cfg_paths.push(entry);
+                    }
                 }
-                cfg_paths.push(entry.into());
             }
         }
 
         cfg_paths
     }
+
+    /// the base `ds-identify.cfg`, followed by every `*.cfg` drop-in under
+    /// `ds-identify.cfg.d/`, in lexical order. Missing files/directories are
+    /// skipped; callers merge these in order, so later entries win per-key.
+    /// An unreadable drop-in directory is warned about and skipped rather
+    /// than aborting the whole run.
+    pub fn di_config_paths(&self, logger: &Logger) -> Vec<PathBuf> {
+        let mut cfg_paths = Vec::new();
+
+        if self.di_config.is_file() {
+            cfg_paths.push(self.di_config.clone());
+        }
+
+        if self.di_config_d.is_dir() {
+            match self.di_config_d.read_dir() {
+                Err(e) => logger.warn(format!(
+                    "failed to read {:?}: {}. skipping drop-ins.",
+                    self.di_config_d, e
+                )),
+                Ok(entries) => {
+                    let mut dropins: Vec<PathBuf> = entries
+                        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                        .filter(|path| path.extension().is_some_and(|ext| ext == "cfg"))
+                        .collect();
+                    dropins.sort();
+                    cfg_paths.extend(dropins);
+                }
+            }
+        }
+
+        cfg_paths
+    }
+
+    /// validates every configured path the way a VFS lookup would: reject a
+    /// relative override outright (a relative `PATH_ROOT`/`PATH_*` would
+    /// otherwise silently join against the process cwd) and resolve
+    /// `.`/`..` components and symlinks, so a chroot/container test root is
+    /// caught up front instead of producing a misleading `PathBuf` the
+    /// first time something reads from it.
+    pub fn resolve(&self) -> Result<(), DsIdentifyError> {
+        for path in self.all_paths() {
+            Self::resolve_path(path)?;
+        }
+        Ok(())
+    }
+
+    fn all_paths(&self) -> [&Path; 19] {
+        [
+            &self.root,
+            &self.run,
+            &self.sys_class_dmi_id,
+            &self.sys_class_block,
+            &self.sys_firmware_efi,
+            &self.dev,
+            &self.var_lib_cloud,
+            &self.di_config,
+            &self.di_config_d,
+            &self.proc_cmdline,
+            &self.proc_1_cmdline,
+            &self.proc_1_environ,
+            &self.proc_uptime,
+            &self.etc_cloud,
+            &self.etc_ci_cfg,
+            &self.etc_ci_cfg_d,
+            &self.run_ci,
+            &self.run_ci_cfg,
+            &self.run_di_result,
+        ]
+    }
+
+    /// normalizes `.`/`..` components lexically, then canonicalizes through
+    /// any symlinks if the path exists (a missing path, common for these
+    /// boot-time probes, is not itself an error).
+    fn resolve_path(path: &Path) -> Result<PathBuf, DsIdentifyError> {
+        if !path.is_absolute() {
+            return Err(DsIdentifyError::not_absolute(path));
+        }
+
+        let mut normalized = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::ParentDir => {
+                    if !normalized.pop() {
+                        return Err(DsIdentifyError::invalid_path(
+                            path,
+                            "`..` escapes the filesystem root",
+                        ));
+                    }
+                }
+                Component::CurDir => (),
+                other => normalized.push(other.as_os_str()),
+            }
+        }
+
+        match normalized.canonicalize() {
+            Ok(canonical) => Ok(canonical),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(normalized),
+            Err(e) => Err(DsIdentifyError::invalid_path(path, e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::MockContext;
+
+    #[test]
+    fn test_resolve_rejects_relative_path_root() {
+        let mut ctx = MockContext::default();
+        ctx.env
+            .insert("PATH_ROOT".to_string(), "relative/root".to_string());
+        let paths = Paths::from_env(&ctx);
+        assert!(matches!(
+            paths.resolve(),
+            Err(DsIdentifyError::NotAbsolute { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_normalizes_dot_dot_components() {
+        let mut ctx = MockContext::default();
+        ctx.env
+            .insert("PATH_ROOT".to_string(), "/tmp/a/../b".to_string());
+        let paths = Paths::from_env(&ctx);
+        assert_eq!(
+            PathBuf::from("/tmp/b"),
+            Paths::resolve_path(Path::new("/tmp/a/../b")).unwrap()
+        );
+        assert!(paths.resolve().is_ok());
+    }
+
+    #[test]
+    fn test_resolve_accepts_root() {
+        let ctx = MockContext::default();
+        let paths = Paths::from_env(&ctx);
+        assert!(paths.resolve().is_ok());
+    }
 }
@@ -0,0 +1,42 @@
+//! Per-OS collection backend, selected once from `UnameInfo::kernel_name` and
+//! held by `Info` as a `Box<dyn PlatformBackend>`, instead of branching on
+//! the kernel name at every call site. Adding a new OS means adding a module
+//! here and a dispatch arm in [`backend_for_kernel_name`]; the collection
+//! orchestration in `info.rs` never changes.
+
+mod freebsd;
+mod linux;
+mod macos;
+
+use std::ffi::OsStr;
+
+use crate::error::DsIdentifyError;
+use crate::info::{FSInfo, Virt};
+use crate::logging::Logger;
+use crate::paths::Paths;
+use crate::smbios::SMBIOS;
+
+pub trait PlatformBackend {
+    fn detect_virt(&self) -> Virt;
+    fn read_smbios(&self, paths: &Paths) -> SMBIOS;
+    fn read_fs_info(&self, logger: &Logger, paths: &Paths, is_container: bool) -> FSInfo;
+    fn read_kernel_cmdline(
+        &self,
+        paths: &Paths,
+        is_container: bool,
+    ) -> Result<String, DsIdentifyError>;
+}
+
+/// picks the backend for the running kernel, the same `uname -s` value
+/// `UnameInfo::kernel_name` already carries. Anything other than `FreeBSD`
+/// or `Darwin` falls back to the Linux backend, matching the pre-refactor
+/// behavior where only FreeBSD had a dedicated branch.
+pub fn backend_for_kernel_name(kernel_name: &OsStr) -> Box<dyn PlatformBackend> {
+    if kernel_name == "FreeBSD" {
+        Box::new(freebsd::FreeBsdBackend)
+    } else if kernel_name == "Darwin" {
+        Box::new(macos::MacosBackend)
+    } else {
+        Box::new(linux::LinuxBackend)
+    }
+}
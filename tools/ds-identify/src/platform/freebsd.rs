@@ -0,0 +1,134 @@
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::process::Command;
+
+use crate::constants::UNAVAILABLE;
+use crate::error::DsIdentifyError;
+use crate::info::{FSInfo, Virt};
+use crate::logging::Logger;
+use crate::paths::Paths;
+use crate::smbios::SMBIOS;
+
+use super::PlatformBackend;
+
+pub struct FreeBsdBackend;
+
+impl PlatformBackend for FreeBsdBackend {
+    fn detect_virt(&self) -> Virt {
+        // Map FreeBSD's vm_guest names to those systemd-detect-virt uses
+        // that don't match up. See
+        // https://github.com/freebsd/freebsd/blob/master/sys/kern/subr_param.c#L144-L160
+        // https://www.freedesktop.org/software/systemd/man/systemd-detect-virt.html
+        //
+        //  systemd    | kern.vm_guest
+        // ---------------------+---------------
+        //  none       | none
+        //  kvm        | kvm
+        //  vmware     | vmware
+        //  microsoft  | hv
+        //  oracle     | vbox
+        //  xen        | xen
+        //  parallels  | parallels
+        //  bhyve      | bhyve
+        //  vm-other   | generic
+        let mut virt = String::from(UNAVAILABLE);
+        if let Ok(output) = Command::new("sysctl")
+            .arg("-qn")
+            .arg("kern.vm_guest")
+            .output()
+        {
+            if let Ok(out) = String::from_utf8(output.stdout) {
+                match out.trim() {
+                    "hv" => virt = String::from("microsoft"),
+                    "vbox" => virt = String::from("oracle"),
+                    "generic" => virt = String::from("vm-other"),
+                    other => virt = other.to_string(),
+                }
+            }
+        }
+        if let Ok(output) = Command::new("sysctl")
+            .arg("-qn")
+            .arg("security.jail.jailed")
+            .output()
+        {
+            if let Ok(out) = String::from_utf8(output.stdout) {
+                if out.trim() == "1" {
+                    virt = String::from("jail");
+                }
+            }
+        }
+        Virt::new(virt)
+    }
+
+    fn read_smbios(&self, _paths: &Paths) -> SMBIOS {
+        SMBIOS {
+            sys_vendor: get_kenv_field(Keys::SysVendor),
+            product_name: get_kenv_field(Keys::ProductName),
+            product_uuid: get_kenv_field(Keys::ProductUuid),
+            product_serial: get_kenv_field(Keys::ProductSerial),
+            chassis_asset_tag: get_kenv_field(Keys::ChassisAssetTag),
+            board_name: get_kenv_field(Keys::BoardName),
+        }
+    }
+
+    fn read_fs_info(&self, _logger: &Logger, _paths: &Paths, is_container: bool) -> FSInfo {
+        // no `blkid`/`/sys/class/block` equivalent on FreeBSD; degrade the
+        // same way the Linux backend does for a container, where the
+        // underlying device signatures aren't reachable either.
+        let unavailable = OsString::from(if is_container {
+            format!("{}:container", UNAVAILABLE)
+        } else {
+            format!("{}:freebsd", UNAVAILABLE)
+        });
+        FSInfo::new(unavailable.clone(), unavailable, None)
+    }
+
+    fn read_kernel_cmdline(
+        &self,
+        _paths: &Paths,
+        _is_container: bool,
+    ) -> Result<String, DsIdentifyError> {
+        Ok(format!("{UNAVAILABLE}:no-cmdline"))
+    }
+}
+
+/// SMBIOS fields FreeBSD's loader exposes as `smbios.*` kenv variables
+/// (there's no `/sys/class/dmi/id` equivalent), mirroring the Linux
+/// backend's DMI `Keys`.
+enum Keys {
+    SysVendor,
+    ProductName,
+    ProductUuid,
+    ProductSerial,
+    ChassisAssetTag,
+    BoardName,
+}
+
+impl Keys {
+    fn get_kenv_name(&self) -> &str {
+        match self {
+            Self::SysVendor => "smbios.system.maker",
+            Self::ProductName => "smbios.system.product",
+            Self::ProductUuid => "smbios.system.uuid",
+            Self::ProductSerial => "smbios.system.serial",
+            Self::ChassisAssetTag => "smbios.chassis.tag",
+            Self::BoardName => "smbios.planar.product",
+        }
+    }
+}
+
+fn get_kenv_field(key: Keys) -> Option<OsString> {
+    let output = Command::new("kenv")
+        .arg("-q")
+        .arg(key.get_kenv_name())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = OsStr::from_bytes(output.stdout.trim_ascii_end());
+    if value.is_empty() {
+        return None;
+    }
+    Some(value.to_os_string())
+}
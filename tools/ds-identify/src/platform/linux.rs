@@ -0,0 +1,296 @@
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::process::Command;
+use std::{fs, path};
+
+use crate::constants::UNAVAILABLE;
+use crate::error::DsIdentifyError;
+use crate::fsprobe::{self, FsType};
+use crate::info::{FSInfo, Virt};
+use crate::logging::Logger;
+use crate::paths::Paths;
+use crate::smbios::SMBIOS;
+
+use super::PlatformBackend;
+
+pub struct LinuxBackend;
+
+impl PlatformBackend for LinuxBackend {
+    fn detect_virt(&self) -> Virt {
+        let mut virt = String::from(UNAVAILABLE);
+        if is_systemd() {
+            let output = Command::new("systemd-detect-virt").output();
+            if let Ok(output) = output {
+                if output.status.success() {
+                    virt = String::from_utf8(output.stdout).unwrap_or(virt);
+                } else if output.stdout == b"none" || output.stderr == b"none" {
+                    virt = String::from("none");
+                }
+            }
+        }
+        Virt::new(virt)
+    }
+
+    fn read_smbios(&self, paths: &Paths) -> SMBIOS {
+        read_from_dmi(&paths.sys_class_dmi_id)
+    }
+
+    fn read_fs_info(&self, logger: &Logger, paths: &Paths, is_container: bool) -> FSInfo {
+        // do not rely on links in /dev/disk which might not be present yet.
+        // Note that blkid < 2.22 (centos6, trusty) do not output DEVNAME.
+        // that means that DI_ISO9660_DEVS will not be set.
+        if is_container {
+            let unavailable_container = OsString::from(format!("{}:container", UNAVAILABLE));
+            // blkid will in a container, or at least currently in lxd
+            // not provide useful information.
+            return FSInfo::new(unavailable_container.clone(), unavailable_container, None);
+        };
+
+        let native_devs = fsprobe::probe_devices(paths);
+        if !native_devs.is_empty() {
+            return fs_info_from_native_probe(&native_devs);
+        }
+
+        match blkid_export(logger) {
+            None => {
+                let unavailable_error = OsString::from(format!("{}:error", UNAVAILABLE));
+                FSInfo::new(
+                    unavailable_error.clone(),
+                    unavailable_error.clone(),
+                    Some(unavailable_error),
+                )
+            }
+            Some(blkid_export_out) => fs_info_from_blkid_export(&blkid_export_out),
+        }
+    }
+
+    fn read_kernel_cmdline(
+        &self,
+        paths: &Paths,
+        is_container: bool,
+    ) -> Result<String, DsIdentifyError> {
+        if is_container {
+            let cmdline = fs::read_to_string(&paths.proc_1_cmdline)
+                .map_err(|e| DsIdentifyError::io(paths.proc_1_cmdline.clone(), e))?;
+            let cmdline = cmdline.replace('\0', " ");
+            if !cmdline.is_empty() {
+                return Ok(cmdline);
+            }
+            Ok(format!("{UNAVAILABLE}:container"))
+        } else if paths.proc_cmdline.is_file() {
+            fs::read_to_string(&paths.proc_cmdline)
+                .map_err(|e| DsIdentifyError::io(paths.proc_cmdline.clone(), e))
+        } else {
+            Ok(format!("{UNAVAILABLE}:no-cmdline"))
+        }
+    }
+}
+
+fn is_systemd() -> bool {
+    path::Path::new("/run/systemd").is_dir()
+}
+
+/// build the same delimited `fs_labels`/`iso9660_devs`/`fs_uuids` format
+/// that `fs_info_from_blkid_export` produces, from the results of the native
+/// signature probe.
+fn fs_info_from_native_probe(devs: &[fsprobe::DeviceInfo]) -> FSInfo {
+    let delim: &OsStr = OsStr::new(",");
+
+    let mut labels = OsString::new();
+    let mut uuids = OsString::new();
+    let mut isodevs = OsString::new();
+
+    for dev in devs {
+        if let Some(label) = &dev.label {
+            labels.push(label);
+            labels.push(delim);
+        }
+
+        if let Some(uuid) = &dev.uuid {
+            uuids.push(uuid);
+            uuids.push(delim);
+        }
+
+        if dev.fstype == FsType::Iso9660 {
+            isodevs.push(&dev.dev);
+            isodevs.push("=");
+            isodevs.push(dev.label.as_deref().unwrap_or(""));
+            isodevs.push(delim);
+        }
+    }
+
+    FSInfo::new(labels, isodevs, Some(uuids))
+}
+
+/// parses the `KEY=value\n`-per-line output of `blkid -o export` at the byte
+/// level, since a device label is arbitrary bytes and not guaranteed to be
+/// valid UTF-8.
+fn fs_info_from_blkid_export(blkid_export_out: &[u8]) -> FSInfo {
+    let delim: &OsStr = OsStr::new(",");
+
+    let mut labels = OsString::new();
+    let mut uuids = OsString::new();
+    let mut isodevs = OsString::new();
+    let mut ftype: Option<&[u8]> = None;
+    let mut dev: Option<&[u8]> = None;
+    let mut label: Option<&[u8]> = None;
+    for line in blkid_export_out.split(|&b| b == b'\n') {
+        if let Some(value) = line.strip_prefix(b"DEVNAME=") {
+            if let Some(dev_prev) = dev {
+                if ftype == Some(b"iso9660".as_slice()) {
+                    push_isodev(&mut isodevs, dev_prev, label, delim);
+                }
+                ftype = None;
+                label = None;
+                dev = Some(value);
+            }
+        } else if let Some(value) = line
+            .strip_prefix(b"LABEL=")
+            .or_else(|| line.strip_prefix(b"LABEL_FATBOOT="))
+        {
+            label = Some(value);
+            labels.push(OsStr::from_bytes(value));
+            labels.push(delim);
+        } else if let Some(value) = line.strip_prefix(b"TYPE=") {
+            ftype = Some(value);
+        } else if let Some(value) = line.strip_prefix(b"UUID=") {
+            uuids.push(OsStr::from_bytes(value));
+            uuids.push(delim);
+        }
+    }
+
+    if let Some(dev_prev) = dev {
+        if ftype == Some(b"iso9660".as_slice()) {
+            push_isodev(&mut isodevs, dev_prev, label, delim);
+        }
+    }
+
+    FSInfo::new(labels, isodevs, Some(uuids))
+}
+
+fn push_isodev(isodevs: &mut OsString, dev: &[u8], label: Option<&[u8]>, delim: &OsStr) {
+    isodevs.push(OsStr::from_bytes(dev));
+    isodevs.push("=");
+    isodevs.push(OsStr::from_bytes(label.unwrap_or(b"")));
+    isodevs.push(delim);
+}
+
+fn blkid_export(logger: &Logger) -> Option<Vec<u8>> {
+    let output = Command::new("blkid")
+        .args(["-c /dev/null -o export"])
+        .output();
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            logger.error(format!("failed to execute blkid: {}", e));
+            return None;
+        }
+    };
+    if !output.status.success() {
+        let ret = output
+            .status
+            .code()
+            .map_or("?".to_string(), |c| c.to_string());
+        logger.error(format!(
+            "failed running [{}]: blkid -c /dev/null -o export",
+            ret
+        ));
+        None
+    } else {
+        Some(output.stdout)
+    }
+}
+
+enum Keys {
+    SysVendor,
+    ProductName,
+    ProductUuid,
+    ProductSerial,
+    ChassisAssetTag,
+    BoardName,
+}
+
+impl Keys {
+    fn get_dmi_field(&self) -> &str {
+        match &self {
+            Self::SysVendor => "system-manufacturer",
+            Self::ProductName => "system-product-name",
+            Self::ProductUuid => "system-uuid",
+            Self::ProductSerial => "system-serial-number",
+            Self::ChassisAssetTag => "chassis-asset-tag",
+            Self::BoardName => panic!("asdfasdf"),
+        }
+    }
+
+    fn get_dmi_file(&self) -> &str {
+        match &self {
+            Self::SysVendor => "sys_vendor",
+            Self::ProductName => "product_name",
+            Self::ProductUuid => "product_uuid",
+            Self::ProductSerial => "product_serial",
+            Self::ChassisAssetTag => "chassis_asset_tag",
+            Self::BoardName => "board_name",
+        }
+    }
+}
+
+fn read_from_dmi(sys_class_dmi_id: &Path) -> SMBIOS {
+    SMBIOS {
+        sys_vendor: get_dmi_field(sys_class_dmi_id, Keys::SysVendor),
+        product_name: get_dmi_field(sys_class_dmi_id, Keys::ProductName),
+        product_uuid: get_dmi_field(sys_class_dmi_id, Keys::ProductUuid),
+        product_serial: get_dmi_field(sys_class_dmi_id, Keys::ProductSerial),
+        chassis_asset_tag: get_dmi_field(sys_class_dmi_id, Keys::ChassisAssetTag),
+        board_name: get_dmi_field(sys_class_dmi_id, Keys::BoardName),
+    }
+}
+
+fn get_dmi_field(sys_class_dmi_id: &Path, key: Keys) -> Option<OsString> {
+    let path = sys_class_dmi_id.join(key.get_dmi_file());
+    if sys_class_dmi_id.is_dir() {
+        if path.is_file() {
+            // DMI fields are raw firmware strings, not guaranteed to be
+            // valid UTF-8, so read bytes rather than `fs::read_to_string`.
+            match fs::read(&path) {
+                Err(e) => match e.kind() {
+                    io::ErrorKind::PermissionDenied => {
+                        return None;
+                    }
+                    _ => panic!("Error reading {}: {}", &path.display(), e),
+                },
+                Ok(content) => {
+                    return Some(OsStr::from_bytes(&content).to_os_string());
+                }
+            }
+        }
+        // if `/sys/class/dmi/id` exists, but not the object we're looking for,
+        // do *not* fallback to dmidecode!
+        return None;
+    }
+    dmi_decode(&key)
+}
+
+fn dmi_decode(sys_field: &Keys) -> Option<OsString> {
+    match &sys_field {
+        Keys::BoardName => None,
+        _ => {
+            let key = sys_field.get_dmi_field();
+            match Command::new("dmidecode")
+                .arg("--quiet")
+                .arg(format!("--string={}", key))
+                .output()
+            {
+                Err(_) => {
+                    // TODO: log error
+                    None
+                }
+                Ok(out) => {
+                    // TODO: check status
+                    Some(OsStr::from_bytes(&out.stdout).to_os_string())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::ffi::OsString;
+use std::process::Command;
+
+use crate::constants::UNAVAILABLE;
+use crate::error::DsIdentifyError;
+use crate::info::{FSInfo, Virt};
+use crate::logging::Logger;
+use crate::paths::Paths;
+use crate::smbios::SMBIOS;
+
+use super::PlatformBackend;
+
+pub struct MacosBackend;
+
+impl PlatformBackend for MacosBackend {
+    fn detect_virt(&self) -> Virt {
+        // macOS has no `systemd-detect-virt` equivalent; `kern.hv_vmm_present`
+        // is the closest analog the kernel exposes, and only says
+        // "some hypervisor" rather than naming which one.
+        let mut virt = String::from(UNAVAILABLE);
+        if let Ok(output) = Command::new("sysctl")
+            .arg("-qn")
+            .arg("kern.hv_vmm_present")
+            .output()
+        {
+            if let Ok(out) = String::from_utf8(output.stdout) {
+                match out.trim() {
+                    "1" => virt = String::from("vm-other"),
+                    "0" => virt = String::from("none"),
+                    _ => (),
+                }
+            }
+        }
+        Virt::new(virt)
+    }
+
+    fn read_smbios(&self, _paths: &Paths) -> SMBIOS {
+        let fields = system_profiler_hardware_fields();
+        SMBIOS {
+            sys_vendor: Some(OsString::from("Apple Inc.")),
+            board_name: None,
+            chassis_asset_tag: None,
+            product_name: fields.get("Model Identifier").cloned().map(OsString::from),
+            product_serial: fields
+                .get("Serial Number (system)")
+                .cloned()
+                .map(OsString::from),
+            product_uuid: fields.get("Hardware UUID").cloned().map(OsString::from),
+        }
+    }
+
+    fn read_fs_info(&self, _logger: &Logger, _paths: &Paths, is_container: bool) -> FSInfo {
+        // no `blkid` equivalent on macOS; degrade the same way the Linux
+        // backend does for a container, where device signatures aren't
+        // reachable either.
+        let unavailable = OsString::from(if is_container {
+            format!("{}:container", UNAVAILABLE)
+        } else {
+            format!("{}:macos", UNAVAILABLE)
+        });
+        FSInfo::new(unavailable.clone(), unavailable, None)
+    }
+
+    fn read_kernel_cmdline(
+        &self,
+        _paths: &Paths,
+        _is_container: bool,
+    ) -> Result<String, DsIdentifyError> {
+        Ok(format!("{UNAVAILABLE}:no-cmdline"))
+    }
+}
+
+/// parses `system_profiler SPHardwareDataType`'s `key: value` lines into a
+/// lookup table; this is macOS's closest equivalent to Linux's
+/// `/sys/class/dmi/id` tree.
+fn system_profiler_hardware_fields() -> std::collections::HashMap<String, String> {
+    let mut fields = std::collections::HashMap::new();
+    let output = match Command::new("system_profiler")
+        .arg("SPHardwareDataType")
+        .output()
+    {
+        Ok(output) if output.status.success() => output.stdout,
+        _ => return fields,
+    };
+    let Ok(text) = String::from_utf8(output) else {
+        return fields;
+    };
+    for line in text.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            fields.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    fields
+}
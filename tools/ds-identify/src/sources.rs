@@ -0,0 +1,4 @@
+pub mod list;
+
+use crate::dss::Datasource;
+pub use list::DatasourceList;
@@ -1,9 +1,11 @@
-use std::{env, fs, io::BufRead, io::BufReader, path::Path};
-
 use crate::{
+    config::Config,
     constants::DI_DSLIST_DEFAULT,
+    context::Context,
+    error::DsIdentifyError,
+    logging::Logger,
     paths::Paths,
-    util::{parse_yaml_array, Logger},
+    util::{parse_yaml_array, unquote},
 };
 
 use super::Datasource;
@@ -16,35 +18,44 @@ impl DatasourceList {
         Self(Vec::new())
     }
 
-    pub fn read(logger: &Logger, paths: &Paths) -> Self {
+    pub fn read(
+        ctx: &dyn Context,
+        logger: &Logger,
+        paths: &Paths,
+        kernel_cmdline: &str,
+    ) -> Result<Self, DsIdentifyError> {
         let mut dslist = None;
 
-        if let Ok(dsname) = env::var("DI_DSNAME") {
+        if let Some(dsname) = ctx.env("DI_DSNAME") {
             dslist = Some(dsname);
         };
 
-        // TODO: kernel cmdline
-        // LP: #1582323. cc:{'datasource_list': ['name']}
-        // more generically cc:<yaml>[end_cc]
-
-        // if DI_DSNAME is set as an envvar or DS_LIST is in the kernel cmdline,
-        // then avoid parsing config.
+        // if DI_DSNAME is set as an envvar, then avoid parsing the kernel
+        // cmdline or config.
         if let Some(dslist) = dslist {
-            return Self::from(&dslist[..]);
+            return Ok(Self::from(&dslist[..]));
         };
 
-        let cfg_paths = paths.etc_ci_cfg_paths();
-        if let Some((found_dslist, path)) = check_config("datasource_list", &cfg_paths[..]) {
+        // LP: #1582323. cc:{'datasource_list': ['name']}
+        // more generically cc:<yaml>[end_cc], or a bare ds=<name> token.
+        // cmdline-derived lists take precedence over file config, same as
+        // DI_DSNAME above.
+        if let Some(dslist) = datasource_list_from_cmdline(kernel_cmdline) {
+            logger.debug(1, format!("kernel cmdline set datasource_list: {dslist:?}"));
+            return Ok(dslist.iter().map(|x| x.as_str().into()).collect());
+        }
+
+        let cfg_paths = paths.etc_ci_cfg_paths(logger);
+        let config = Config::read(ctx, logger, &cfg_paths);
+        if let Some((found_dslist, path)) = config.datasource_list() {
             logger.debug(
                 1,
-                format!("{:?} set datasource_list: {}", path, found_dslist),
+                format!("{:?} set datasource_list: {:?}", path, found_dslist),
             );
-            let dslist = parse_yaml_array(&found_dslist);
-            let dslist = dslist.iter().map(|x| (*x).into()).collect();
-            return Self(dslist);
-        };
+            return Ok(found_dslist.iter().map(|s| s.as_str().into()).collect());
+        }
 
-        DatasourceList::default()
+        Ok(DatasourceList::default())
     }
 
     pub fn push(&mut self, ds: Datasource) {
@@ -120,30 +131,74 @@ impl FromIterator<Datasource> for DatasourceList {
     }
 }
 
-/// somewhat hackily read through paths for `key`
+/// Derive a datasource list from the kernel cmdline, if one is present.
 ///
-/// currently does not respect any hierarchy in searching for key.
-fn check_config<'a, P: AsRef<Path>>(key: &str, paths: &'a [P]) -> Option<(String, &'a Path)> {
-    let mut value_path = None;
-
-    for f in paths.iter().filter(|p| p.as_ref().is_file()) {
-        let stream = BufReader::new(fs::File::open(f).unwrap());
-        for line in stream.lines() {
-            let line = line.unwrap();
-
-            // remove trailing comments or full line comments
-            let line = match line.split_once('#') {
-                Some((line, _)) => line,
-                None => &line,
-            }
-            .trim();
-
-            if let Some((k, v)) = line.split_once(':') {
-                if key == k.trim() {
-                    value_path = Some((v.trim().to_owned(), f.as_ref()));
-                }
-            };
+/// Recognizes a bare `ds=<name>` token, which forces a single-entry list,
+/// and cloud-init's embedded `cc:<yaml>[end_cc]` block, whose
+/// `datasource_list` key is read with [`parse_yaml_array`]. `ds=` is
+/// checked first since it is the more specific directive.
+fn datasource_list_from_cmdline(kernel_cmdline: &str) -> Option<Vec<String>> {
+    for tok in kernel_cmdline.split_whitespace() {
+        if let Some(name) = tok.strip_prefix("ds=") {
+            return Some(vec![name.to_string()]);
         }
     }
-    value_path
+
+    let cc_block = extract_cc_block(kernel_cmdline)?;
+    // the kernel cmdline cannot contain real newlines, so cloud-init encodes
+    // them as the literal two-character sequence `\n` inside the cc: block.
+    let cc_block = cc_block.replace("\\n", "\n");
+    for line in cc_block.lines() {
+        // also accepts the flow-mapping form, {'datasource_list': ['name']}
+        let line = line.trim().trim_start_matches('{').trim_end_matches('}');
+        let Some((key, val)) = line.split_once(':') else {
+            continue;
+        };
+        if unquote(key.trim()) == "datasource_list" {
+            return Some(
+                parse_yaml_array(val.trim())
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+            );
+        }
+    }
+    None
+}
+
+/// extract the text between a `cc:` marker and a following `end_cc` marker
+/// (or the end of the string, if there is none) from the kernel cmdline.
+fn extract_cc_block(kernel_cmdline: &str) -> Option<&str> {
+    let start = kernel_cmdline.find("cc:")? + "cc:".len();
+    let rest = &kernel_cmdline[start..];
+    Some(match rest.find("end_cc") {
+        Some(end) => &rest[..end],
+        None => rest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datasource_list_from_cmdline_ds_token() {
+        assert_eq!(
+            Some(vec!["nocloud".to_string()]),
+            datasource_list_from_cmdline("root=/dev/sda1 ds=nocloud ro")
+        );
+    }
+
+    #[test]
+    fn test_datasource_list_from_cmdline_cc_block() {
+        assert_eq!(
+            Some(vec!["NoCloud".to_string(), "Ec2".to_string()]),
+            datasource_list_from_cmdline("ro cc:datasource_list: 'NoCloud', 'Ec2'\\nend_cc quiet")
+        );
+    }
+
+    #[test]
+    fn test_datasource_list_from_cmdline_none() {
+        assert_eq!(None, datasource_list_from_cmdline("root=/dev/sda1 ro quiet"));
+    }
 }
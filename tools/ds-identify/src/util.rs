@@ -1,12 +1,4 @@
-use std::io::Write;
-use std::{
-    cell::RefCell,
-    env,
-    ffi::OsStr,
-    fs,
-    io::{self, BufWriter},
-    path,
-};
+use std::{env, ffi::OsStr};
 
 pub fn ensure_sane_path() {
     let mut path = env::var("PATH").expect("$PATH set");
@@ -45,8 +37,8 @@ pub fn unquote(val: &str) -> &str {
 ///   ['1'] or [1]
 ///   '1', '2'
 pub fn parse_yaml_array(val: &str) -> Vec<&str> {
-    let val = val.strip_prefix('[').unwrap_or_else(|| val);
-    let val = val.strip_prefix(']').unwrap_or_else(|| val);
+    let val = val.strip_prefix('[').unwrap_or(val);
+    let val = val.strip_suffix(']').unwrap_or(val);
     val.split(',').map(|tok| unquote(tok.trim())).collect()
 }
 
@@ -54,79 +46,29 @@ pub fn get_env_var<K: AsRef<OsStr>>(key: K, default: String) -> String {
     env::var(key).unwrap_or_else(|_| default)
 }
 
-pub struct Logger {
-    level: i32,
-    writer: RefCell<BufWriter<Box<dyn io::Write>>>,
-}
-
-impl Logger {
-    pub fn new<S: AsRef<str>>(di_log: S) -> Self {
-        let level: i32 = get_env_var("DEBUG_LEVEL", String::from("-1"))
-            .parse()
-            .unwrap();
-
-        let mut log_file = di_log.as_ref().trim();
-
-        match log_file {
-            "stderr" => (),
-            _ => {
-                if log_file.contains("/") {
-                    // Create parent directories
-                    // TODO: unit test
-                    if let Some(parent_dir) = path::PathBuf::from(log_file).parent() {
-                        if let Err(_) = ::std::fs::create_dir_all(parent_dir) {
-                            eprintln!("ERROR: cannot write to {}", di_log.as_ref());
-                            log_file = "stderr";
-                        }
-                    }
-                }
-            }
-        }
-
-        let writer: BufWriter<Box<dyn io::Write>> = match log_file {
-            "stderr" => {
-                dbg!("log to stderr");
-                BufWriter::new(Box::new(io::stderr().lock()))
-            }
-            _ => {
-                dbg!("log to file: {}", log_file);
-                let file = fs::OpenOptions::new()
-                    .append(true)
-                    .create(true)
-                    .open(log_file)
-                    .unwrap();
-                BufWriter::new(Box::new(file))
+/// a JSON string literal for `val`, escaping `"` and `\`.
+pub fn json_string(val: &str) -> String {
+    let mut out = String::with_capacity(val.len() + 2);
+    out.push('"');
+    for c in val.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
             }
-        };
-        let writer = RefCell::new(writer);
-
-        Self { level, writer }
-    }
-
-    fn log<S: AsRef<str>>(&self, level: i32, msg: S) {
-        if level < self.level {
-            return;
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
         }
-        self.write_always(msg);
-    }
-
-    pub fn debug<S: AsRef<str>>(&self, level: i32, msg: S) {
-        self.log(level, msg);
-    }
-
-    pub fn warn<S: AsRef<str>>(&self, msg: S) {
-        let msg = format!("WARN: {}", msg.as_ref());
-        self.debug(0, &msg);
-        eprintln!("{}", &msg);
-    }
-    pub fn error<S: AsRef<str>>(&self, msg: S) {
-        let msg = format!("ERROR: {}", msg.as_ref());
-        self.debug(0, &msg);
-        eprintln!("{}", &msg);
     }
+    out.push('"');
+    out
+}
 
-    pub fn write_always<S: AsRef<str>>(&self, msg: S) {
-        write!(self.writer.borrow_mut(), "{}\n", msg.as_ref()).expect("writable file");
+/// `json_string`, or the literal `null` for `None`.
+pub fn json_opt_string(val: Option<&str>) -> String {
+    match val {
+        Some(val) => json_string(val),
+        None => "null".to_string(),
     }
 }
 
@@ -147,4 +89,26 @@ mod utils {
         assert_eq!(vec!["a", "b"], parse_yaml_array("a,b"));
         assert_eq!(vec!["a", "b"], parse_yaml_array("'a' ,  \"b\""));
     }
+
+    #[test]
+    fn test_parse_yaml_array_brackets() {
+        assert_eq!(
+            vec!["NoCloud", "Ec2"],
+            parse_yaml_array("['NoCloud', 'Ec2']")
+        );
+        assert_eq!(vec!["a", "b"], parse_yaml_array("[a,b]"));
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!("\"a\"", json_string("a"));
+        assert_eq!("\"a\\\"b\"", json_string("a\"b"));
+        assert_eq!("\"a\\\\b\"", json_string("a\\b"));
+    }
+
+    #[test]
+    fn test_json_opt_string() {
+        assert_eq!("null", json_opt_string(None));
+        assert_eq!("\"a\"", json_opt_string(Some("a")));
+    }
 }